@@ -0,0 +1,157 @@
+//! A lowered `ModPath` type that is like the syntactic `ast::Path`, but
+//! a) has its hygiene stripped and b) is amenable to arena-allocation.
+
+mod lower;
+
+use std::{iter, sync::Arc};
+
+use hir_expand::{
+    hygiene::Hygiene,
+    name::{AsName, Name},
+};
+use ra_db::CrateId;
+use ra_syntax::ast::{self, AstNode};
+
+use crate::type_ref::{TypeBound, TypeRef};
+
+pub(crate) use lower::lower_use_tree;
+
+/// A path in a `use` item or expression, with the generic args of each segment.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Path {
+    pub mod_path: ModPath,
+    /// Invariant: the same length as `self.mod_path.segments`
+    pub generic_args: Vec<Option<Arc<GenericArgs>>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ModPath {
+    pub kind: PathKind,
+    pub segments: Vec<Name>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum PathKind {
+    Plain,
+    /// Absolute path
+    Abs,
+    /// `crate::` prefix
+    Crate,
+    /// `self::`, or `self` in `use self::foo` / `use self;`
+    Self_,
+    /// `super::`
+    Super,
+    /// `$crate` from macro expansion
+    DollarCrate(CrateId),
+    /// `<T>::foo` or `<T as Trait>::foo`
+    Type(Box<TypeRef>),
+}
+
+impl Path {
+    /// Converts an `ast::Path` to `Path`. Works with use trees, but a path
+    /// nested in a use tree list is lowered without the prefix of the
+    /// enclosing trees (`expand_use_item` threads that prefix).
+    /// DEPRECATED: It does not handle `$crate` based path from macro call.
+    pub fn from_ast(path: ast::Path) -> Option<Path> {
+        lower::lower_path(path, &Hygiene::new_unhygienic())
+    }
+
+    /// Converts an `ast::Path` to `Path`. Works with use trees, but a path
+    /// nested in a use tree list is lowered without the prefix of the
+    /// enclosing trees (`expand_use_item` threads that prefix).
+    /// It correctly handles `$crate` based path from macro call.
+    pub fn from_src(path: ast::Path, hygiene: &Hygiene) -> Option<Path> {
+        lower::lower_path(path, hygiene)
+    }
+
+    /// Converts a known mod path to `Path`.
+    pub(crate) fn from_simple_segments(
+        kind: PathKind,
+        segments: impl IntoIterator<Item = Name>,
+    ) -> Path {
+        let segments = segments.into_iter().collect::<Vec<_>>();
+        let generic_args = iter::repeat(None).take(segments.len()).collect();
+        Path { mod_path: ModPath { kind, segments }, generic_args }
+    }
+
+    /// Calls `cb` with all paths, represented by this use item.
+    pub(crate) fn expand_use_item(
+        item_src: crate::InFile<ast::UseItem>,
+        hygiene: &Hygiene,
+        mut cb: impl FnMut(Path, &ast::UseTree, bool, Option<Name>),
+    ) {
+        if let Some(tree) = item_src.value.use_tree() {
+            lower::lower_use_tree(None, tree, hygiene, &mut cb);
+        }
+    }
+
+    pub fn is_self(&self) -> bool {
+        self.mod_path.kind == PathKind::Self_ && self.mod_path.segments.is_empty()
+    }
+
+    /// If this path is a single identifier, like `foo`, return its name.
+    pub fn as_ident(&self) -> Option<&Name> {
+        if self.mod_path.kind != PathKind::Plain || self.generic_args.iter().any(|it| it.is_some())
+        {
+            return None;
+        }
+        self.mod_path.segments.first()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct GenericArgs {
+    pub args: Vec<GenericArg>,
+    /// This specifies whether the args contain a Self type as the first
+    /// element. This is the case for path segments like `<T as Trait>`,
+    /// where `T` is actually a type parameter for the path `Trait` which
+    /// is inserted as the first parameter when we bring the associated
+    /// type into scope.
+    pub has_self_type: bool,
+    /// Associated type bindings like in `Iterator<Item = T>`, plus
+    /// associated type bounds like in `Iterator<Item: Clone>`. An entry can
+    /// carry an equality binding, a set of bounds, or (in malformed code)
+    /// neither.
+    pub bindings: Vec<(Name, Option<TypeRef>, Vec<TypeBound>)>,
+}
+
+impl GenericArgs {
+    pub(crate) fn empty() -> GenericArgs {
+        GenericArgs { args: Vec::new(), has_self_type: false, bindings: Vec::new() }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum GenericArg {
+    Type(TypeRef),
+    Lifetime(LifetimeRef),
+    Const(ConstRef),
+}
+
+/// A lowered const generic argument, e.g. the `4` in `Array<T, 4>`. We don't
+/// evaluate it here, just keep the source text around until the const
+/// parameter it's bound to is known. Only arguments the parser marks as const
+/// (literals, blocks) end up here; a bare identifier such as the `N` in
+/// `Matrix<N, M>` is lowered as a `GenericArg::Type`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ConstRef {
+    pub text: String,
+}
+
+impl ConstRef {
+    pub(crate) fn new(arg: &ast::ConstArg) -> Self {
+        ConstRef { text: arg.syntax().text().to_string() }
+    }
+}
+
+/// A lowered reference to a lifetime, e.g. the `'a` in `Foo<'a, T>`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct LifetimeRef {
+    pub name: Name,
+}
+
+impl LifetimeRef {
+    pub(crate) fn new(lifetime: &ast::Lifetime) -> Self {
+        LifetimeRef { name: lifetime.as_name() }
+    }
+}
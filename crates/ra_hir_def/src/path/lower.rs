@@ -12,15 +12,29 @@ use hir_expand::{
 use ra_syntax::ast::{self, AstNode, TypeAscriptionOwner};
 
 use crate::{
-    path::{GenericArg, GenericArgs, ModPath, Path, PathKind},
-    type_ref::TypeRef,
+    path::{ConstRef, GenericArg, GenericArgs, LifetimeRef, ModPath, Path, PathKind},
+    type_ref::{TypeBound, TypeRef},
 };
 
 pub(super) use lower_use::lower_use_tree;
 
 /// Converts an `ast::Path` to `Path`. Works with use trees.
 /// It correctly handles `$crate` based path from macro call.
-pub(super) fn lower_path(mut path: ast::Path, hygiene: &Hygiene) -> Option<Path> {
+/// A path nested in a use tree list gets no prefix from the enclosing trees.
+pub(super) fn lower_path(path: ast::Path, hygiene: &Hygiene) -> Option<Path> {
+    lower_path_with_prefix(path, None, hygiene)
+}
+
+/// Like `lower_path`, but takes an explicit `prefix` path to prepend instead
+/// of reconstructing it by climbing `ast::UseTreeList` ancestors. Used by
+/// `lower_use_tree` to thread the accumulated prefix top-down through nested
+/// use trees, e.g. for `use a::{b::c, d::{e, f}}` the prefix passed in for
+/// `b::c` is `a`, and for `e`/`f` it's `a::d`.
+pub(super) fn lower_path_with_prefix(
+    mut path: ast::Path,
+    prefix: Option<&Path>,
+    hygiene: &Hygiene,
+) -> Option<Path> {
     let mut kind = PathKind::Plain;
     let mut segments = Vec::new();
     let mut generic_args = Vec::new();
@@ -103,41 +117,62 @@ pub(super) fn lower_path(mut path: ast::Path, hygiene: &Hygiene) -> Option<Path>
                 break;
             }
         }
-        path = match qualifier(&path) {
+        path = match path.qualifier() {
             Some(it) => it,
             None => break,
         };
     }
     segments.reverse();
     generic_args.reverse();
-    let mod_path = ModPath { kind, segments };
-    return Some(Path { mod_path, generic_args });
 
-    fn qualifier(path: &ast::Path) -> Option<ast::Path> {
-        if let Some(q) = path.qualifier() {
-            return Some(q);
-        }
-        // FIXME: this bottom up traversal is not too precise.
-        // Should we handle do a top-down analysis, recording results?
-        let use_tree_list = path.syntax().ancestors().find_map(ast::UseTreeList::cast)?;
-        let use_tree = use_tree_list.parent_use_tree();
-        use_tree.path()
+    if let Some(prefix) = prefix {
+        kind = prefix.mod_path.kind.clone();
+        let mut prefix_segments = prefix.mod_path.segments.clone();
+        prefix_segments.append(&mut segments);
+        segments = prefix_segments;
+        let mut prefix_args = prefix.generic_args.clone();
+        prefix_args.append(&mut generic_args);
+        generic_args = prefix_args;
     }
+
+    let mod_path = ModPath { kind, segments };
+    Some(Path { mod_path, generic_args })
 }
 
 pub(super) fn lower_generic_args(node: ast::TypeArgList) -> Option<GenericArgs> {
+    // Collect lifetime and type args together, keyed by their position in the
+    // source, so that e.g. `Foo<'a, T>` and `Foo<T, 'a>` keep their original
+    // ordering instead of always sorting lifetimes first.
     let mut args = Vec::new();
+    for lifetime_arg in node.lifetime_args() {
+        if let Some(lifetime) = lifetime_arg.lifetime() {
+            let arg = GenericArg::Lifetime(LifetimeRef::new(&lifetime));
+            args.push((lifetime_arg.syntax().text_range().start(), arg));
+        }
+    }
     for type_arg in node.type_args() {
         let type_ref = TypeRef::from_ast_opt(type_arg.type_ref());
-        args.push(GenericArg::Type(type_ref));
+        let arg = GenericArg::Type(type_ref);
+        args.push((type_arg.syntax().text_range().start(), arg));
+    }
+    for const_arg in node.const_args() {
+        let arg = GenericArg::Const(ConstRef::new(&const_arg));
+        args.push((const_arg.syntax().text_range().start(), arg));
     }
-    // lifetimes ignored for now
+    args.sort_by_key(|(offset, _)| *offset);
+    let args = args.into_iter().map(|(_, arg)| arg).collect();
+
     let mut bindings = Vec::new();
     for assoc_type_arg in node.assoc_type_args() {
         if let Some(name_ref) = assoc_type_arg.name_ref() {
             let name = name_ref.as_name();
-            let type_ref = TypeRef::from_ast_opt(assoc_type_arg.type_ref());
-            bindings.push((name, type_ref));
+            let type_ref = assoc_type_arg.type_ref().map(TypeRef::from_ast);
+            let bounds = if let Some(bound_list) = assoc_type_arg.type_bound_list() {
+                bound_list.bounds().map(TypeBound::from_ast).collect()
+            } else {
+                Vec::new()
+            };
+            bindings.push((name, type_ref, bounds));
         }
     }
     if args.is_empty() && bindings.is_empty() {
@@ -165,8 +200,8 @@ fn lower_generic_args_from_fn_path(
         args.push(arg);
     }
     if let Some(ret_type) = ret_type {
-        let type_ref = TypeRef::from_ast_opt(ret_type.type_ref());
-        bindings.push((name![Output], type_ref))
+        let type_ref = ret_type.type_ref().map(TypeRef::from_ast);
+        bindings.push((name![Output], type_ref, Vec::new()))
     }
     if args.is_empty() && bindings.is_empty() {
         None
@@ -174,3 +209,61 @@ fn lower_generic_args_from_fn_path(
         Some(GenericArgs { args, has_self_type: false, bindings })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use ra_syntax::SourceFile;
+
+    use super::*;
+
+    fn lower_type_arg_list(path: &str) -> GenericArgs {
+        let file = SourceFile::parse(&format!("type __Test = {};", path)).tree();
+        let path = file.syntax().descendants().find_map(ast::Path::cast).unwrap();
+        let type_arg_list = path.segment().unwrap().type_arg_list().unwrap();
+        lower_generic_args(type_arg_list).unwrap()
+    }
+
+    #[test]
+    fn lifetime_and_type_args_keep_source_order() {
+        let args = lower_type_arg_list("Foo<'a, T>").args;
+        assert!(matches!(args[0], GenericArg::Lifetime(_)));
+        assert!(matches!(args[1], GenericArg::Type(_)));
+
+        let args = lower_type_arg_list("Foo<T, 'a>").args;
+        assert!(matches!(args[0], GenericArg::Type(_)));
+        assert!(matches!(args[1], GenericArg::Lifetime(_)));
+    }
+
+    #[test]
+    fn const_args_keep_source_order() {
+        let args = lower_type_arg_list("Array<T, 4>").args;
+        assert!(matches!(args[0], GenericArg::Type(_)));
+        assert!(matches!(args[1], GenericArg::Const(_)));
+
+        // Bare identifiers can't be told apart from types syntactically, so
+        // `N` and `M` are lowered as type args.
+        let args = lower_type_arg_list("Matrix<N, M>").args;
+        assert!(matches!(args[0], GenericArg::Type(_)));
+        assert!(matches!(args[1], GenericArg::Type(_)));
+    }
+
+    #[test]
+    fn assoc_type_bound_is_lowered_as_bounds_not_equality() {
+        let bindings = lower_type_arg_list("Iterator<Item: Clone + Send>").bindings;
+        assert_eq!(bindings.len(), 1);
+        let (name, type_ref, bounds) = &bindings[0];
+        assert_eq!(name.to_string(), "Item");
+        assert!(type_ref.is_none());
+        assert_eq!(bounds.len(), 2);
+    }
+
+    #[test]
+    fn assoc_type_equality_binding_has_no_bounds() {
+        let bindings = lower_type_arg_list("Iterator<Item = u32>").bindings;
+        assert_eq!(bindings.len(), 1);
+        let (name, type_ref, bounds) = &bindings[0];
+        assert_eq!(name.to_string(), "Item");
+        assert!(type_ref.is_some());
+        assert!(bounds.is_empty());
+    }
+}
@@ -0,0 +1,99 @@
+//! Lowers a single complex `use` item, such as `use foo::{bar, baz::*}`, into
+//! a flat list of paths. The accumulated prefix is threaded top-down through
+//! nested use trees, rather than having each leaf path reconstruct it by
+//! climbing `ast::UseTreeList` ancestors.
+
+use hir_expand::{
+    hygiene::Hygiene,
+    name::{AsName, Name},
+};
+use ra_syntax::ast;
+
+use super::lower_path_with_prefix;
+use crate::path::Path;
+
+pub(crate) fn lower_use_tree(
+    prefix: Option<Path>,
+    tree: ast::UseTree,
+    hygiene: &Hygiene,
+    cb: &mut dyn FnMut(Path, &ast::UseTree, bool, Option<Name>),
+) {
+    if let Some(use_tree_list) = tree.use_tree_list() {
+        let prefix = match tree.path() {
+            // E.g. `use {inner}` or `use ...::{{{inner}}}`.
+            None => prefix,
+            // E.g. `use a::{b::c, d::{e, f}}`: the prefix passed down to `b::c`,
+            // `e` and `f` is `a`, `a::d` and `a::d` respectively.
+            Some(path) => match lower_path_with_prefix(path, prefix.as_ref(), hygiene) {
+                Some(it) => Some(it),
+                None => return, // FIXME: report errors somewhere
+            },
+        };
+        for child_tree in use_tree_list.use_trees() {
+            lower_use_tree(prefix.clone(), child_tree, hygiene, cb);
+        }
+    } else {
+        let is_glob = tree.star_token().is_some();
+        if let Some(ast_path) = tree.path() {
+            // E.g. `use a::{self::b, c}` - the leading `self::` adds no
+            // segment: `lower_path_with_prefix` replaces its path kind with
+            // the prefix's, so this lowers to `a::b`.
+            if let Some(path) = lower_path_with_prefix(ast_path, prefix.as_ref(), hygiene) {
+                let alias = tree.rename().and_then(|rename| rename.name()).map(|it| it.as_name());
+                cb(path, &tree, is_glob, alias)
+            }
+        } else if is_glob {
+            // E.g. `use a::b::{*}` - the leaf `*` carries no path of its own,
+            // the full prefix built up so far *is* the globbed path.
+            if let Some(prefix) = prefix {
+                cb(prefix, &tree, true, None)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ra_syntax::{ast::UseItem, AstNode, SourceFile};
+
+    use super::*;
+
+    fn lower(use_item: &str) -> Vec<(Path, bool)> {
+        let file = SourceFile::parse(use_item).tree();
+        let use_tree =
+            file.syntax().descendants().find_map(UseItem::cast).unwrap().use_tree().unwrap();
+        let mut result = Vec::new();
+        let hygiene = Hygiene::new_unhygienic();
+        lower_use_tree(None, use_tree, &hygiene, &mut |path, _tree, is_glob, _alias| {
+            result.push((path, is_glob));
+        });
+        result
+    }
+
+    #[test]
+    fn plain_glob_import_is_marked_as_glob() {
+        let lowered = lower("use a::b::*;");
+        assert_eq!(lowered.len(), 1);
+        let (path, is_glob) = &lowered[0];
+        assert!(is_glob, "`use a::b::*;` must be lowered as a glob import");
+        assert_eq!(path.mod_path.segments.len(), 2);
+    }
+
+    #[test]
+    fn braced_glob_import_is_marked_as_glob() {
+        let lowered = lower("use a::b::{*};");
+        assert_eq!(lowered.len(), 1);
+        let (_path, is_glob) = &lowered[0];
+        assert!(is_glob);
+    }
+
+    #[test]
+    fn nested_use_tree_prefix_is_threaded_top_down() {
+        let lowered = lower("use a::{b::c, d::{e, f}};");
+        assert_eq!(lowered.len(), 3);
+        for (path, is_glob) in &lowered {
+            assert!(!is_glob);
+            assert_eq!(path.mod_path.segments.len(), 3);
+        }
+    }
+}
@@ -0,0 +1,135 @@
+//! HIR for references to types. Paths in these are not yet resolved. They
+//! can be directly created from an `ast::TypeRef`, without further queries.
+
+use ra_syntax::ast;
+
+use crate::path::{LifetimeRef, Path};
+
+#[derive(Clone, PartialEq, Eq, Hash, Debug, Copy)]
+pub enum Mutability {
+    Shared,
+    Mut,
+}
+
+impl Mutability {
+    pub fn from_mutable(mutable: bool) -> Mutability {
+        if mutable {
+            Mutability::Mut
+        } else {
+            Mutability::Shared
+        }
+    }
+}
+
+/// Compare with `ty::Ty`
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub enum TypeRef {
+    Never,
+    Placeholder,
+    Tuple(Vec<TypeRef>),
+    Path(Path),
+    RawPtr(Box<TypeRef>, Mutability),
+    Reference(Box<TypeRef>, Mutability),
+    Array(Box<TypeRef>),
+    Slice(Box<TypeRef>),
+    /// A fn pointer. Last element of the vector is the return type.
+    Fn(Vec<TypeRef>),
+    ImplTrait(Vec<TypeBound>),
+    DynTrait(Vec<TypeBound>),
+    Error,
+}
+
+impl TypeRef {
+    pub fn from_ast(node: ast::TypeRef) -> Self {
+        match node {
+            ast::TypeRef::ParenType(inner) => TypeRef::from_ast_opt(inner.type_ref()),
+            ast::TypeRef::TupleType(inner) => {
+                TypeRef::Tuple(inner.fields().map(TypeRef::from_ast).collect())
+            }
+            ast::TypeRef::NeverType(..) => TypeRef::Never,
+            ast::TypeRef::PathType(inner) => inner
+                .path()
+                .and_then(|path| Path::from_ast(path))
+                .map(TypeRef::Path)
+                .unwrap_or(TypeRef::Error),
+            ast::TypeRef::PointerType(inner) => {
+                let inner_ty = TypeRef::from_ast_opt(inner.type_ref());
+                let mutability = Mutability::from_mutable(inner.mut_token().is_some());
+                TypeRef::RawPtr(Box::new(inner_ty), mutability)
+            }
+            ast::TypeRef::ArrayType(inner) => {
+                TypeRef::Array(Box::new(TypeRef::from_ast_opt(inner.type_ref())))
+            }
+            ast::TypeRef::SliceType(inner) => {
+                TypeRef::Slice(Box::new(TypeRef::from_ast_opt(inner.type_ref())))
+            }
+            ast::TypeRef::ReferenceType(inner) => {
+                let inner_ty = TypeRef::from_ast_opt(inner.type_ref());
+                let mutability = Mutability::from_mutable(inner.mut_token().is_some());
+                TypeRef::Reference(Box::new(inner_ty), mutability)
+            }
+            ast::TypeRef::PlaceholderType(_) => TypeRef::Placeholder,
+            ast::TypeRef::FnPointerType(inner) => {
+                let ret_ty = TypeRef::from_ast_opt(inner.ret_type().and_then(|rt| rt.type_ref()));
+                let mut params: Vec<_> = inner
+                    .param_list()
+                    .map(|pl| {
+                        pl.params().map(|p| TypeRef::from_ast_opt(p.ascribed_type())).collect()
+                    })
+                    .unwrap_or_else(Vec::new);
+                params.push(ret_ty);
+                TypeRef::Fn(params)
+            }
+            ast::TypeRef::ForType(inner) => TypeRef::from_ast_opt(inner.type_ref()),
+            ast::TypeRef::ImplTraitType(inner) => {
+                TypeRef::ImplTrait(TypeBound::from_ast_type_bound_list(inner.type_bound_list()))
+            }
+            ast::TypeRef::DynTraitType(inner) => {
+                TypeRef::DynTrait(TypeBound::from_ast_type_bound_list(inner.type_bound_list()))
+            }
+        }
+    }
+
+    pub(crate) fn from_ast_opt(node: Option<ast::TypeRef>) -> Self {
+        match node {
+            Some(node) => TypeRef::from_ast(node),
+            None => TypeRef::Error,
+        }
+    }
+
+    pub(crate) fn unit() -> TypeRef {
+        TypeRef::Tuple(Vec::new())
+    }
+}
+
+/// A single bound, e.g. the `Clone` or the `'a` in `T: Clone + 'a`.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub enum TypeBound {
+    Path(Path),
+    Lifetime(LifetimeRef),
+    Error,
+}
+
+impl TypeBound {
+    pub(crate) fn from_ast(node: ast::TypeBound) -> Self {
+        match node.type_ref() {
+            Some(ast::TypeRef::PathType(path_type)) => path_type
+                .path()
+                .and_then(|path| Path::from_ast(path))
+                .map(TypeBound::Path)
+                .unwrap_or(TypeBound::Error),
+            Some(_) => TypeBound::Error,
+            None => match node.lifetime() {
+                Some(lifetime) => TypeBound::Lifetime(LifetimeRef::new(&lifetime)),
+                None => TypeBound::Error,
+            },
+        }
+    }
+
+    pub(crate) fn from_ast_type_bound_list(node: Option<ast::TypeBoundList>) -> Vec<Self> {
+        match node {
+            Some(bound_list) => bound_list.bounds().map(TypeBound::from_ast).collect(),
+            None => Vec::new(),
+        }
+    }
+}